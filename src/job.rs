@@ -0,0 +1,335 @@
+// Recurring-schedule layer on top of the one-shot `Runnable` trait. The API is
+// modeled on skedge/schedule: users declare jobs fluently with `every(10).minutes()`,
+// `every(1).day().at("14:30")`, or `every(1).week().on(Weekday::Mon)`, hand them a
+// `Box<dyn Runnable>`, and let an `IntervalScheduler` fire them on a `run_pending(now)` tick.
+use crate::task::tasks::{Runnable, RunnableStatus};
+use chrono::{DateTime, Datelike, Duration, Local, NaiveTime, Weekday};
+use std::collections::HashSet;
+use std::fs::File;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub(crate) enum Unit {
+    Second,
+    Minute,
+    Hour,
+    Day,
+    Week,
+}
+
+impl Unit {
+    // Length of a single `interval` step in this unit.
+    fn duration(&self, interval: u32) -> Duration {
+        let interval = interval as i64;
+        match self {
+            Unit::Second => Duration::seconds(interval),
+            Unit::Minute => Duration::minutes(interval),
+            Unit::Hour => Duration::hours(interval),
+            Unit::Day => Duration::days(interval),
+            Unit::Week => Duration::weeks(interval),
+        }
+    }
+}
+
+// Parse an `at` time-of-day from the `HH:MM:SS`/`MM:SS`/`:SS` forms skedge accepts.
+// Returns `None` on anything malformed so the builder can ignore an unusable value.
+fn parse_at(spec: &str) -> Option<NaiveTime> {
+    let parts: Vec<&str> = spec.split(':').collect();
+    let (h, m, s) = match parts.as_slice() {
+        [h, m, s] => (h, m, s),
+        // `:SS` splits into ["", "SS"]; treat the empty leading field as hour/minute 0
+        // so the seconds-only form maps to `00:00:SS`.
+        ["", s] => (&"0", &"0", s),
+        [m, s] => (&"0", m, s),
+        _ => return None,
+    };
+    let hour: u32 = h.trim().parse().ok()?;
+    let minute: u32 = m.trim().parse().ok()?;
+    let second: u32 = s.trim().parse().ok()?;
+    NaiveTime::from_hms_opt(hour, minute, second)
+}
+
+pub(crate) struct Job {
+    interval: u32,
+    unit: Option<Unit>,
+    at_time: Option<NaiveTime>,
+    weekday: Option<Weekday>,
+    tags: HashSet<String>,
+    until: Option<DateTime<Local>>,
+    next_run: Option<DateTime<Local>>,
+    task: Option<Box<dyn Runnable>>,
+}
+
+// Entry point for the fluent builder, e.g. `every(10).minutes()`.
+pub(crate) fn every(interval: u32) -> Job {
+    Job {
+        interval,
+        unit: None,
+        at_time: None,
+        weekday: None,
+        tags: HashSet::new(),
+        until: None,
+        next_run: None,
+        task: None,
+    }
+}
+
+impl Job {
+    fn with_unit(mut self, unit: Unit) -> Job {
+        self.unit = Some(unit);
+        self
+    }
+
+    pub(crate) fn second(self) -> Job {
+        self.with_unit(Unit::Second)
+    }
+    pub(crate) fn seconds(self) -> Job {
+        self.with_unit(Unit::Second)
+    }
+    pub(crate) fn minute(self) -> Job {
+        self.with_unit(Unit::Minute)
+    }
+    pub(crate) fn minutes(self) -> Job {
+        self.with_unit(Unit::Minute)
+    }
+    pub(crate) fn hour(self) -> Job {
+        self.with_unit(Unit::Hour)
+    }
+    pub(crate) fn hours(self) -> Job {
+        self.with_unit(Unit::Hour)
+    }
+    pub(crate) fn day(self) -> Job {
+        self.with_unit(Unit::Day)
+    }
+    pub(crate) fn days(self) -> Job {
+        self.with_unit(Unit::Day)
+    }
+    pub(crate) fn week(self) -> Job {
+        self.with_unit(Unit::Week)
+    }
+    pub(crate) fn weeks(self) -> Job {
+        self.with_unit(Unit::Week)
+    }
+
+    // Pin the job to a wall-clock time of day. Daily and weekly jobs read a two-field
+    // spec such as `14:30` as `HH:MM`; other units read it as `MM:SS`. Ignored if the
+    // spec is malformed.
+    pub(crate) fn at(mut self, spec: &str) -> Job {
+        self.at_time = match self.unit {
+            Some(Unit::Day) | Some(Unit::Week)
+                if spec.split(':').count() == 2 && !spec.starts_with(':') =>
+            {
+                parse_at(&format!("{}:00", spec))
+            }
+            _ => parse_at(spec),
+        };
+        self
+    }
+
+    // Pin a weekly job to a given weekday.
+    pub(crate) fn on(mut self, weekday: Weekday) -> Job {
+        self.weekday = Some(weekday);
+        self
+    }
+
+    // Attach an arbitrary string tag for later cancel-by-tag.
+    pub(crate) fn tag(mut self, tag: &str) -> Job {
+        self.tags.insert(tag.to_string());
+        self
+    }
+
+    // Stop scheduling once `when` has passed.
+    pub(crate) fn until(mut self, when: DateTime<Local>) -> Job {
+        self.until = Some(when);
+        self
+    }
+
+    // Attach the unit of work. The finished job is then registered with
+    // `IntervalScheduler::schedule`.
+    pub(crate) fn run(mut self, task: Box<dyn Runnable>) -> Job {
+        self.task = Some(task);
+        self
+    }
+
+    fn period(&self) -> Duration {
+        self.unit
+            .expect("job has no unit; call e.g. .minutes() on it")
+            .duration(self.interval)
+    }
+
+    // Compute the first `next_run` relative to `now`. Following the skedge model, snap
+    // `now` itself to the nearest `at`/weekday slot first so a job registered before
+    // today's slot still fires today (or this week). A slot that has already passed is
+    // rolled forward by `snap` to the next day (or week); a full period is only added
+    // when there is no `at`/weekday to anchor to.
+    fn schedule_first_run(&mut self, now: DateTime<Local>) {
+        let mut next = self.snap(now, now);
+        if next <= now {
+            next = self.snap(next + self.period(), now);
+        }
+        self.next_run = Some(next);
+    }
+
+    // Advance `next_run` by one interval, measured from the previously scheduled time
+    // rather than from `now`, so a slow tick does not make the cadence drift.
+    fn advance(&mut self, now: DateTime<Local>) {
+        let base = self.next_run.unwrap_or(now);
+        let mut next = base + self.period();
+        next = self.snap(next, now);
+        self.next_run = Some(next);
+    }
+
+    // Snap a candidate time to the wall-clock slot that satisfies the configured `at`
+    // time-of-day and weekday on or after its own date, moving it one more day (for
+    // `at`) or one more week (for a weekday) when that slot is not after `now`. With
+    // neither set the candidate is returned unchanged.
+    fn snap(&self, mut candidate: DateTime<Local>, now: DateTime<Local>) -> DateTime<Local> {
+        if let Some(at) = self.at_time {
+            candidate = candidate
+                .date_naive()
+                .and_time(at)
+                .and_local_timezone(Local)
+                .single()
+                .unwrap_or(candidate);
+            if candidate <= now {
+                candidate += Duration::days(1);
+            }
+        }
+        if let Some(weekday) = self.weekday {
+            let ahead = (7 + weekday.num_days_from_monday() as i64
+                - candidate.weekday().num_days_from_monday() as i64)
+                % 7;
+            candidate += Duration::days(ahead);
+            if candidate <= now {
+                candidate += Duration::weeks(1);
+            }
+        }
+        candidate
+    }
+
+    fn is_due(&self, now: DateTime<Local>) -> bool {
+        match self.next_run {
+            Some(next) => next <= now,
+            None => false,
+        }
+    }
+
+    fn is_expired(&self, now: DateTime<Local>) -> bool {
+        match self.until {
+            Some(deadline) => now >= deadline,
+            None => false,
+        }
+    }
+
+    fn execute(&mut self) -> RunnableStatus {
+        match self.task.as_mut() {
+            // The recurring layer does not own per-run output paths, so the captured
+            // output is discarded to /dev/null; callers wanting files should wire a
+            // `CommandTask` with its own redirection.
+            Some(task) => {
+                // Don't spawn a second process on top of a slow previous run: that would
+                // overwrite the tracked child and orphan it. Skip until it has finished.
+                if matches!(task.status(), RunnableStatus::Running) {
+                    return RunnableStatus::Running;
+                }
+                let sink = || File::create("/dev/null");
+                match (sink(), sink()) {
+                    (Ok(out), Ok(err)) => task.start(out, err),
+                    _ => RunnableStatus::Error("could not open /dev/null for job output".to_string()),
+                }
+            }
+            None => RunnableStatus::Error("job has no attached task".to_string()),
+        }
+    }
+}
+
+pub(crate) struct IntervalScheduler {
+    jobs: Vec<Job>,
+}
+
+impl IntervalScheduler {
+    pub(crate) fn new() -> IntervalScheduler {
+        IntervalScheduler { jobs: Vec::new() }
+    }
+
+    // Register a job, computing its first run relative to `now`.
+    pub(crate) fn schedule(&mut self, mut job: Job, now: DateTime<Local>) {
+        job.schedule_first_run(now);
+        self.jobs.push(job);
+    }
+
+    // Fire every due job, reschedule it, and drop any that have reached their deadline.
+    pub(crate) fn run_pending(&mut self, now: DateTime<Local>) {
+        for job in self.jobs.iter_mut() {
+            if job.is_due(now) {
+                job.execute();
+                job.advance(now);
+            }
+        }
+        self.jobs.retain(|job| !job.is_expired(now));
+    }
+
+    // Cancel every job carrying the given tag.
+    pub(crate) fn cancel_by_tag(&mut self, tag: &str) {
+        self.jobs.retain(|job| !job.tags.contains(tag));
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.jobs.len()
+    }
+}
+
+#[cfg(test)]
+use chrono::{NaiveDate, TimeZone};
+
+#[test]
+fn test_parse_at_forms() {
+    assert_eq!(parse_at("14:30:15"), NaiveTime::from_hms_opt(14, 30, 15));
+    assert_eq!(parse_at("30:15"), NaiveTime::from_hms_opt(0, 30, 15));
+    assert_eq!(parse_at(":15"), NaiveTime::from_hms_opt(0, 0, 15));
+    assert_eq!(parse_at("nonsense"), None);
+}
+
+#[test]
+fn test_interval_does_not_drift() {
+    let now = Local.with_ymd_and_hms(2024, 3, 4, 12, 0, 0).unwrap();
+    let mut job = every(10).minutes();
+    job.schedule_first_run(now);
+    assert_eq!(job.next_run, Some(now + Duration::minutes(10)));
+    // A late tick five minutes after the run was due still advances from the
+    // scheduled time, keeping the cadence on the :10 boundary.
+    job.advance(now + Duration::minutes(15));
+    assert_eq!(job.next_run, Some(now + Duration::minutes(20)));
+}
+
+#[test]
+fn test_daily_at_snaps_to_time_of_day() {
+    let now = Local.with_ymd_and_hms(2024, 3, 4, 12, 0, 0).unwrap();
+    let mut job = every(1).day().at("14:30");
+    job.schedule_first_run(now);
+    let next = job.next_run.unwrap();
+    assert_eq!(next.time(), NaiveTime::from_hms_opt(14, 30, 0).unwrap());
+    // The 14:30 slot is still ahead of the 12:00 registration, so it fires today.
+    assert_eq!(next.date_naive(), now.date_naive());
+}
+
+#[test]
+fn test_weekly_on_snaps_to_weekday() {
+    // 2024-03-04 is a Monday.
+    let now = Local.with_ymd_and_hms(2024, 3, 4, 12, 0, 0).unwrap();
+    let mut job = every(1).week().on(Weekday::Wed);
+    job.schedule_first_run(now);
+    let next = job.next_run.unwrap();
+    assert_eq!(next.weekday(), Weekday::Wed);
+    // The coming Wednesday is two days out, not a full week plus change.
+    assert_eq!(next.date_naive(), NaiveDate::from_ymd_opt(2024, 3, 6).unwrap());
+}
+
+#[test]
+fn test_cancel_by_tag() {
+    let now = Local.with_ymd_and_hms(2024, 3, 4, 12, 0, 0).unwrap();
+    let mut scheduler = IntervalScheduler::new();
+    scheduler.schedule(every(1).hour().tag("backup"), now);
+    scheduler.schedule(every(1).day().tag("report"), now);
+    scheduler.cancel_by_tag("backup");
+    assert_eq!(scheduler.len(), 1);
+}
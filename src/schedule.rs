@@ -1,8 +1,11 @@
-use chrono::{NaiveTime, Timelike, Weekday};
+use crate::task::tasks::{Runnable, RunnableStatus};
+use chrono::{DateTime, Datelike, Duration, Local, NaiveDate, NaiveTime, Timelike, Weekday};
 use std::collections::HashMap;
+use std::fs::File;
+use std::path::PathBuf;
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
-enum TimeStatus {
+pub(crate) enum TimeStatus {
     Free,
     Busy,
 }
@@ -18,7 +21,7 @@ struct DaySchedule {
     interval_size: usize,
 }
 
-struct WeekSchedule {
+pub(crate) struct WeekSchedule {
     days: HashMap<Weekday, DaySchedule>,
 }
 
@@ -39,7 +42,7 @@ fn index_to_time_range(index: usize, interval_size: usize) -> (NaiveTime, NaiveT
 
     let end_minute = (index + 1) * interval_size;
     let end = if end_minute >= 1440 {
-        NaiveTime::from_hms_opt(11, 59, 59).unwrap()
+        NaiveTime::from_hms_opt(23, 59, 59).unwrap()
     } else {
         NaiveTime::from_hms_opt(
             (end_minute / 60).try_into().unwrap(),
@@ -83,6 +86,18 @@ impl DaySchedule {
         let index = time_to_range_index(time, self.interval_size);
         self.blocks[index].block_type
     }
+    // Coalesce consecutive same-status blocks into maximal `(start, end, status)`
+    // intervals, so a day reads as a handful of free/busy spans rather than 96 cells.
+    fn intervals(&self) -> Vec<(NaiveTime, NaiveTime, TimeStatus)> {
+        let mut out: Vec<(NaiveTime, NaiveTime, TimeStatus)> = Vec::new();
+        for block in &self.blocks {
+            match out.last_mut() {
+                Some(last) if last.2 == block.block_type => last.1 = block.end,
+                _ => out.push((block.start, block.end, block.block_type)),
+            }
+        }
+        out
+    }
     fn set_time_status(&mut self, start_time: NaiveTime, end_time: NaiveTime, status: TimeStatus) {
         let start_index = time_to_range_index(start_time, self.interval_size);
         let end_index = time_to_range_index(end_time, self.interval_size);
@@ -90,6 +105,37 @@ impl DaySchedule {
             self.blocks[i].block_type = status;
         }
     }
+    // Coalesce consecutive `Free` blocks into maximal contiguous intervals and return
+    // the window that actually contains `time`, provided it leaves at least
+    // `min_duration` before the next `Busy` region. Used to decide whether a
+    // long-running task started now can finish before that region begins. A day can hold
+    // several free spans, so the window containing `time` is not necessarily the
+    // earliest one.
+    fn find_free_window(
+        &self,
+        time: NaiveTime,
+        min_duration: Duration,
+    ) -> Option<(NaiveTime, NaiveTime)> {
+        let mut run: Option<(NaiveTime, NaiveTime)> = None;
+        for block in &self.blocks {
+            if block.block_type == TimeStatus::Free {
+                run = match run {
+                    Some((start, _)) => Some((start, block.end)),
+                    None => Some((block.start, block.end)),
+                };
+            } else if let Some((start, end)) = run.take() {
+                if start <= time && time < end && end - time >= min_duration {
+                    return Some((start, end));
+                }
+            }
+        }
+        match run {
+            Some((start, end)) if start <= time && time < end && end - time >= min_duration => {
+                Some((start, end))
+            }
+            _ => None,
+        }
+    }
 }
 
 impl Default for WeekSchedule {
@@ -108,7 +154,7 @@ impl Default for WeekSchedule {
 }
 
 impl WeekSchedule {
-    fn get_time_status(&self, day: Weekday, time: NaiveTime) -> TimeStatus {
+    pub(crate) fn get_time_status(&self, day: Weekday, time: NaiveTime) -> TimeStatus {
         self.days[&day].get_time_status(time)
     }
     fn set_time_status(
@@ -123,6 +169,182 @@ impl WeekSchedule {
             .unwrap()
             .set_time_status(start_time, end_time, status);
     }
+    // Mark a `[start, end)` window on `day` as `Free`, leaving the rest of the week as it
+    // was (all `Busy` in a fresh `WeekSchedule::default()`).
+    // Convenience for config loaders that describe availability as free windows.
+    pub(crate) fn mark_free(&mut self, day: Weekday, start: NaiveTime, end: NaiveTime) {
+        self.set_time_status(day, start, end, TimeStatus::Free);
+    }
+    // The free window on `day` that contains `time` and leaves at least `min_duration`
+    // before the next `Busy` region, if any.
+    fn find_free_window(
+        &self,
+        day: Weekday,
+        time: NaiveTime,
+        min_duration: Duration,
+    ) -> Option<(NaiveTime, NaiveTime)> {
+        self.days[&day].find_free_window(time, min_duration)
+    }
+    // Project the recurring template onto the concrete week beginning `week_start`
+    // (a Monday), returning the free/busy intervals as real local `DateTime`s. This
+    // lets callers ask "what are my free slots the week of March 4th?" and feed the
+    // result into the interval scheduler.
+    pub(crate) fn describe(
+        &self,
+        week_start: NaiveDate,
+    ) -> Vec<(DateTime<Local>, DateTime<Local>, TimeStatus)> {
+        let mut out = Vec::new();
+        for offset in 0..7 {
+            let date = week_start + Duration::days(offset);
+            if let Some(schedule) = self.days.get(&date.weekday()) {
+                for (start, end, status) in schedule.intervals() {
+                    let begin = date.and_time(start).and_local_timezone(Local).single();
+                    let finish = date.and_time(end).and_local_timezone(Local).single();
+                    if let (Some(begin), Some(finish)) = (begin, finish) {
+                        out.push((begin, finish, status));
+                    }
+                }
+            }
+        }
+        out
+    }
+}
+
+// Monday that anchors the ISO-style week containing `date`. Uses the
+// days-from-Monday offset `weekday().number_from_monday() - 1`.
+fn week_start(date: NaiveDate) -> NaiveDate {
+    let days_from_monday = date.weekday().number_from_monday() as i64 - 1;
+    date - Duration::days(days_from_monday)
+}
+
+// Parse a week reference into the Monday that anchors it. Accepts explicit
+// `Weekday_Week_Year` tokens like `Mon_05_2024` and a few fuzzy phrases
+// ("this week", "next week", "last week", "next monday"). Returns `None` on anything
+// it does not understand.
+pub(crate) fn parse_week(input: &str) -> Option<NaiveDate> {
+    let trimmed = input.trim();
+    if let Some(date) = parse_week_token(trimmed) {
+        return Some(week_start(date));
+    }
+    parse_fuzzy_week(&trimmed.to_lowercase())
+}
+
+fn parse_week_token(token: &str) -> Option<NaiveDate> {
+    let parts: Vec<&str> = token.split('_').collect();
+    let [day, week, year] = parts.as_slice() else {
+        return None;
+    };
+    let weekday: Weekday = day.parse().ok()?;
+    let week: u32 = week.parse().ok()?;
+    let year: i32 = year.parse().ok()?;
+    NaiveDate::from_isoywd_opt(year, week, weekday)
+}
+
+fn parse_fuzzy_week(phrase: &str) -> Option<NaiveDate> {
+    let today = Local::now().date_naive();
+    let this_week = week_start(today);
+    match phrase {
+        "this week" => Some(this_week),
+        "next week" => Some(this_week + Duration::weeks(1)),
+        "last week" => Some(this_week - Duration::weeks(1)),
+        _ => {
+            // "next <weekday>": the next occurrence strictly after today, anchored back
+            // to its Monday so every arm honors the Monday-anchored contract `describe`
+            // relies on.
+            let weekday: Weekday = phrase.strip_prefix("next ")?.parse().ok()?;
+            let ahead = (7 + weekday.num_days_from_monday() as i64
+                - today.weekday().num_days_from_monday() as i64
+                - 1)
+                % 7
+                + 1;
+            Some(week_start(today + Duration::days(ahead)))
+        }
+    }
+}
+
+// Drives a queue of tasks against a `WeekSchedule`, only launching work while the
+// current wall-clock slot is `Free`. When a block flips from `Free` to `Busy` the
+// running task is killed so it does not bleed into protected time. Each task declares a
+// `min_duration`; it is only launched if the current free block still has at least that
+// much room before the next `Busy` region, so a long job is never started when it cannot
+// finish in time. Callers tick the loop with the current `Weekday`/`NaiveTime`; output is
+// captured to files named after the task's queue position in `output_dir`, preserving
+// the crate's file-based capture.
+pub(crate) struct WindowScheduler {
+    queue: Vec<Box<dyn Runnable>>,
+    min_durations: Vec<Duration>,
+    schedule: WeekSchedule,
+    output_dir: PathBuf,
+    running: Option<usize>,
+}
+
+impl WindowScheduler {
+    pub(crate) fn new(schedule: WeekSchedule, output_dir: PathBuf) -> WindowScheduler {
+        WindowScheduler {
+            queue: Vec::new(),
+            min_durations: Vec::new(),
+            schedule,
+            output_dir,
+            running: None,
+        }
+    }
+
+    // Enqueue a task that needs at least `min_duration` of uninterrupted free time to
+    // run. Pass `Duration::zero()` for a task with no minimum.
+    pub(crate) fn enqueue(&mut self, task: Box<dyn Runnable>, min_duration: Duration) {
+        self.queue.push(task);
+        self.min_durations.push(min_duration);
+    }
+
+    // Advance the loop by one observation of the clock. During a `Busy` slot any
+    // running task is killed; during a `Free` slot a finished task is retired and the
+    // next waiting task is launched.
+    pub(crate) fn tick(&mut self, day: Weekday, time: NaiveTime) {
+        match self.schedule.get_time_status(day, time) {
+            TimeStatus::Busy => {
+                if let Some(index) = self.running.take() {
+                    self.queue[index].kill();
+                }
+            }
+            TimeStatus::Free => {
+                if let Some(index) = self.running {
+                    match self.queue[index].status() {
+                        RunnableStatus::Running => return,
+                        _ => self.running = None,
+                    }
+                }
+                self.launch_next(day, time);
+            }
+        }
+    }
+
+    fn launch_next(&mut self, day: Weekday, time: NaiveTime) {
+        let next = self
+            .queue
+            .iter()
+            .position(|task| matches!(task.status(), RunnableStatus::Waiting));
+        if let Some(index) = next {
+            // Only launch if the current free block leaves at least `min_duration` before
+            // the next `Busy` region; otherwise hold the task until a long-enough window.
+            if !self.fits(day, time, self.min_durations[index]) {
+                return;
+            }
+            let out = self.output_dir.join(format!("task_{}.out", index));
+            let err = self.output_dir.join(format!("task_{}.err", index));
+            if let (Ok(out), Ok(err)) = (File::create(out), File::create(err)) {
+                self.queue[index].start(out, err);
+                self.running = Some(index);
+            }
+        }
+    }
+
+    // True when the free window on `day` that contains `time` still has at least
+    // `min_duration` of room before the next `Busy` region.
+    fn fits(&self, day: Weekday, time: NaiveTime, min_duration: Duration) -> bool {
+        self.schedule
+            .find_free_window(day, time, min_duration)
+            .is_some()
+    }
 }
 
 #[test]
@@ -145,7 +367,7 @@ fn test_index_to_time_range() {
         index_to_time_range(95, 15),
         (
             NaiveTime::from_hms_opt(23, 45, 0).unwrap(),
-            NaiveTime::from_hms_opt(11, 59, 59).unwrap()
+            NaiveTime::from_hms_opt(23, 59, 59).unwrap()
         )
     );
 }
@@ -218,3 +440,64 @@ fn test_week_block_set() {
         TimeStatus::Busy
     );
 }
+
+#[test]
+fn test_find_free_window() {
+    let t = |h, m| NaiveTime::from_hms_opt(h, m, 0).unwrap();
+    let mut day_schedule = DaySchedule::new();
+    // No free time yet.
+    assert_eq!(day_schedule.find_free_window(t(2, 30), Duration::minutes(30)), None);
+
+    // Two free spans in the same day: 02:00–04:00 and 10:00–12:00.
+    day_schedule.set_time_status(t(2, 0), t(4, 0), TimeStatus::Free);
+    day_schedule.set_time_status(t(10, 0), t(12, 0), TimeStatus::Free);
+
+    // A time inside the early span coalesces its 15-minute intervals into the window.
+    assert_eq!(
+        day_schedule.find_free_window(t(2, 0), Duration::hours(1)),
+        Some((t(2, 0), t(4, 0)))
+    );
+    // A time inside the later span returns *that* window, not the earliest one.
+    assert_eq!(
+        day_schedule.find_free_window(t(10, 30), Duration::hours(1)),
+        Some((t(10, 0), t(12, 0)))
+    );
+    // Too little room left before the window ends.
+    assert_eq!(day_schedule.find_free_window(t(11, 30), Duration::hours(1)), None);
+    // A time that falls in a `Busy` span has no window.
+    assert_eq!(day_schedule.find_free_window(t(6, 0), Duration::minutes(30)), None);
+}
+
+#[test]
+fn test_parse_week_token() {
+    // ISO week 5 of 2024 begins on Monday 2024-01-29.
+    assert_eq!(
+        parse_week("Mon_05_2024"),
+        NaiveDate::from_ymd_opt(2024, 1, 29)
+    );
+    // A mid-week token still anchors back to that week's Monday.
+    assert_eq!(
+        parse_week("Wed_05_2024"),
+        NaiveDate::from_ymd_opt(2024, 1, 29)
+    );
+    assert_eq!(parse_week("garbage"), None);
+}
+
+#[test]
+fn test_describe_projects_free_interval() {
+    let mut week = WeekSchedule::default();
+    let start = NaiveTime::from_hms_opt(2, 0, 0).unwrap();
+    let end = NaiveTime::from_hms_opt(4, 0, 0).unwrap();
+    week.mark_free(Weekday::Mon, start, end);
+
+    // Monday 2024-01-29.
+    let week_start = NaiveDate::from_ymd_opt(2024, 1, 29).unwrap();
+    let intervals = week.describe(week_start);
+    // The Monday free window appears as a concrete dated interval.
+    let free = intervals
+        .iter()
+        .find(|(begin, _, status)| *status == TimeStatus::Free && begin.date_naive() == week_start);
+    assert!(free.is_some());
+    let (begin, _, _) = free.unwrap();
+    assert_eq!(begin.time(), start);
+}
@@ -1,18 +1,21 @@
+use crate::task::command::{escalating_kill, signal_group, LaunchSpec, DEFAULT_KILL_GRACE};
 use crate::task::tasks::{Runnable, RunnableStatus};
 use std::cell::RefCell;
 use std::fs::File;
 use std::io::Result;
 use std::os::unix::fs::PermissionsExt;
 use std::path::PathBuf;
-use std::process::{Child, Command};
+use std::process::Child;
+use std::time::Duration;
 
 pub(crate) struct BashScriptTask {
     script: PathBuf,
     proc: Option<RefCell<Child>>,
+    grace: Duration,
 }
 
 impl BashScriptTask {
-    fn new(script: PathBuf) -> Result<BashScriptTask> {
+    pub(crate) fn new(script: PathBuf) -> Result<BashScriptTask> {
         // Check that the script actually exists
         // and is executable
         if !script.exists() {
@@ -41,24 +44,31 @@ impl BashScriptTask {
         Ok(BashScriptTask {
             script: script,
             proc: None,
+            grace: DEFAULT_KILL_GRACE,
         })
     }
+
+    // Override how long `kill` waits after SIGTERM before escalating to SIGKILL.
+    pub(crate) fn with_grace(mut self, grace: Duration) -> BashScriptTask {
+        self.grace = grace;
+        self
+    }
 }
 
 impl Runnable for BashScriptTask {
     fn start(&mut self, output: File, error: File) -> RunnableStatus {
-        let command = Command::new("bash")
-            .arg(&self.script)
-            .stdout(output)
-            .stderr(error)
-            .spawn();
-
-        if let Err(e) = command {
-            return RunnableStatus::Error(format!("Failed to start script: {}", e));
+        // The executable-bit check in `new` is the bash-specific validation; the actual
+        // launch reuses the general `LaunchSpec` path as `bash <script>`.
+        let mut spec = LaunchSpec::new(PathBuf::from("bash"));
+        spec.args = vec![self.script.to_string_lossy().into_owned()];
+
+        match spec.spawn(output, error) {
+            Ok(child) => {
+                self.proc = Some(RefCell::new(child));
+                RunnableStatus::Running
+            }
+            Err(e) => RunnableStatus::Error(format!("Failed to start script: {}", e)),
         }
-
-        self.proc = Some(RefCell::new(command.unwrap()));
-        RunnableStatus::Running
     }
 
     fn status(&self) -> RunnableStatus {
@@ -78,15 +88,22 @@ impl Runnable for BashScriptTask {
         }
     }
     fn kill(&mut self) -> RunnableStatus {
-        match &mut self.proc {
-            Some(proc) => {
-                let status = proc.borrow_mut().kill();
-                if status.is_ok() {
-                    RunnableStatus::Killed
-                } else {
-                    RunnableStatus::Error("Failed to kill script".to_string())
-                }
-            }
+        // SIGTERM the script's process group, giving forked subprocesses a chance to
+        // clean up, then SIGKILL the group if the script itself outlives the grace period.
+        match &self.proc {
+            Some(proc) => escalating_kill(proc, self.grace),
+            None => RunnableStatus::Waiting,
+        }
+    }
+
+    fn signal(&mut self, sig: i32) -> RunnableStatus {
+        match &self.proc {
+            // Report the script's actual post-signal state rather than assuming it is
+            // still running.
+            Some(proc) => match signal_group(proc, sig) {
+                Ok(()) => self.status(),
+                Err(e) => RunnableStatus::Error(format!("Failed to send signal {}: {}", sig, e)),
+            },
             None => RunnableStatus::Waiting,
         }
     }
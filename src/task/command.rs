@@ -0,0 +1,243 @@
+// A general-purpose task that launches an arbitrary executable, not just an
+// executable bash script. The launch parameters are collected in a reusable
+// `LaunchSpec` — program, argv, environment overrides, working directory and
+// optional piped stdin — mirroring the env+stdin control in rust-analyzer's
+// `not_bash` helper. `BashScriptTask` is now one validation strategy that builds
+// such a spec (`bash <script>`); this is another that spawns the program directly.
+use crate::task::tasks::{Runnable, RunnableStatus};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{Result, Write};
+use std::os::unix::process::CommandExt;
+use std::path::PathBuf;
+use std::process::{Child, Command, Stdio};
+use std::thread::sleep;
+use std::time::{Duration, Instant};
+
+// How long `kill` waits for a SIGTERM'd process group to exit before escalating to
+// SIGKILL. Tasks may override this per instance.
+pub(crate) const DEFAULT_KILL_GRACE: Duration = Duration::from_secs(5);
+
+// Send `sig` to the whole process group led by `child`. Every task is spawned in its
+// own group (see `LaunchSpec::spawn`), so a negative pid reaches any subprocesses a
+// shell script forked, not just the direct child.
+// Send `sig` to the whole process group led by `child`, returning the delivery result.
+// The caller decides what status to surface — signal delivery itself is not a lifecycle
+// state, so this does not claim the task is `Running`.
+pub(crate) fn signal_group(child: &RefCell<Child>, sig: i32) -> std::io::Result<()> {
+    let pgid = child.borrow().id() as i32;
+    let rc = unsafe { libc::kill(-pgid, sig) };
+    if rc == 0 {
+        Ok(())
+    } else {
+        Err(std::io::Error::last_os_error())
+    }
+}
+
+// watchexec-style escalation: SIGTERM the group, poll the direct child with `try_wait`
+// for `grace`, then, if that child is still alive, SIGKILL the group and reap the child
+// so it does not linger as a zombie.
+pub(crate) fn escalating_kill(child: &RefCell<Child>, grace: Duration) -> RunnableStatus {
+    if let Err(e) = signal_group(child, libc::SIGTERM) {
+        return RunnableStatus::Error(format!("Failed to send SIGTERM: {}", e));
+    }
+    let deadline = Instant::now() + grace;
+    while Instant::now() < deadline {
+        match child.borrow_mut().try_wait() {
+            Ok(Some(_)) => return RunnableStatus::Killed,
+            Ok(None) => sleep(Duration::from_millis(50)),
+            Err(e) => return RunnableStatus::Error(format!("Failed to poll process: {}", e)),
+        }
+    }
+    if let Err(e) = signal_group(child, libc::SIGKILL) {
+        return RunnableStatus::Error(format!("Failed to send SIGKILL: {}", e));
+    }
+    // Wait on the SIGKILL'd process so the kernel reaps it immediately rather than
+    // leaving a zombie until the next `status()` or drop.
+    if let Err(e) = child.borrow_mut().wait() {
+        return RunnableStatus::Error(format!("Failed to reap process: {}", e));
+    }
+    RunnableStatus::Killed
+}
+
+pub(crate) struct LaunchSpec {
+    pub(crate) program: PathBuf,
+    pub(crate) args: Vec<String>,
+    pub(crate) env: HashMap<String, String>,
+    pub(crate) cwd: Option<PathBuf>,
+    pub(crate) stdin: Option<Vec<u8>>,
+}
+
+impl LaunchSpec {
+    pub(crate) fn new(program: PathBuf) -> LaunchSpec {
+        LaunchSpec {
+            program,
+            args: Vec::new(),
+            env: HashMap::new(),
+            cwd: None,
+            stdin: None,
+        }
+    }
+
+    // Spawn the configured process with output/error redirected to the given files.
+    // Any piped stdin is written in full and then closed so the child sees EOF.
+    pub(crate) fn spawn(&self, output: File, error: File) -> Result<Child> {
+        let mut command = Command::new(&self.program);
+        command.args(&self.args).stdout(output).stderr(error);
+        // Start the child as the leader of its own process group so `kill`/`signal` can
+        // target the whole group, reaping any subprocesses it forks.
+        command.process_group(0);
+        for (key, value) in &self.env {
+            command.env(key, value);
+        }
+        if let Some(cwd) = &self.cwd {
+            command.current_dir(cwd);
+        }
+        if self.stdin.is_some() {
+            command.stdin(Stdio::piped());
+        }
+
+        let mut child = command.spawn()?;
+        if let Some(bytes) = &self.stdin {
+            // The handle is dropped at the end of this block, closing the pipe; output
+            // is captured to files so there is no risk of a stdout/stdin deadlock.
+            let mut pipe = child.stdin.take().expect("stdin was requested but not piped");
+            pipe.write_all(bytes)?;
+        }
+        Ok(child)
+    }
+}
+
+pub(crate) struct CommandTask {
+    spec: LaunchSpec,
+    proc: Option<RefCell<Child>>,
+    grace: Duration,
+}
+
+impl CommandTask {
+    pub(crate) fn new(spec: LaunchSpec) -> CommandTask {
+        CommandTask {
+            spec,
+            proc: None,
+            grace: DEFAULT_KILL_GRACE,
+        }
+    }
+
+    // Override how long `kill` waits after SIGTERM before escalating to SIGKILL.
+    pub(crate) fn with_grace(mut self, grace: Duration) -> CommandTask {
+        self.grace = grace;
+        self
+    }
+}
+
+impl Runnable for CommandTask {
+    fn start(&mut self, output: File, error: File) -> RunnableStatus {
+        match self.spec.spawn(output, error) {
+            Ok(child) => {
+                self.proc = Some(RefCell::new(child));
+                RunnableStatus::Running
+            }
+            Err(e) => RunnableStatus::Error(format!("Failed to start command: {}", e)),
+        }
+    }
+
+    fn status(&self) -> RunnableStatus {
+        match &self.proc {
+            Some(proc) => {
+                if let Some(status) = proc.borrow_mut().try_wait().expect("Failed to get status") {
+                    if status.success() {
+                        RunnableStatus::Finished
+                    } else {
+                        RunnableStatus::Error(format!("Command failed with status {}", status))
+                    }
+                } else {
+                    RunnableStatus::Running
+                }
+            }
+            None => RunnableStatus::Waiting,
+        }
+    }
+
+    fn kill(&mut self) -> RunnableStatus {
+        match &self.proc {
+            Some(proc) => escalating_kill(proc, self.grace),
+            None => RunnableStatus::Waiting,
+        }
+    }
+
+    fn signal(&mut self, sig: i32) -> RunnableStatus {
+        match &self.proc {
+            // On successful delivery report the task's actual post-signal lifecycle
+            // state rather than assuming it is still running.
+            Some(proc) => match signal_group(proc, sig) {
+                Ok(()) => self.status(),
+                Err(e) => RunnableStatus::Error(format!("Failed to send signal {}: {}", sig, e)),
+            },
+            None => RunnableStatus::Waiting,
+        }
+    }
+}
+
+// Some tests
+//
+#[test]
+pub(crate) fn test_command_runs() {
+    use std::thread::sleep;
+    let mut spec = LaunchSpec::new(PathBuf::from("/bin/echo"));
+    spec.args = vec!["hello".to_string()];
+    let mut task = CommandTask::new(spec);
+
+    let output = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("scripts")
+        .join("command_echo.out");
+    let error = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("scripts")
+        .join("command_echo.err");
+    let output_file = File::create(&output).expect("Failed to create output file");
+    let error_file = File::create(&error).expect("Failed to create error file");
+
+    let status = task.start(output_file, error_file);
+    assert!(matches!(status, RunnableStatus::Running));
+    sleep(std::time::Duration::from_secs(1));
+    assert!(matches!(task.status(), RunnableStatus::Finished));
+
+    let output = std::fs::read_to_string(output).expect("Failed to read output file");
+    assert_eq!(output, "hello\n");
+}
+
+#[test]
+pub(crate) fn test_command_env_and_stdin() {
+    use std::thread::sleep;
+    // The shell prints an overridden environment variable, then `cat` echoes the piped
+    // stdin straight to stdout.
+    let mut spec = LaunchSpec::new(PathBuf::from("/bin/sh"));
+    spec.args = vec![
+        "-c".to_string(),
+        "printf '%s\\n' \"$LTASK_GREETING\"; cat".to_string(),
+    ];
+    spec.env
+        .insert("LTASK_GREETING".to_string(), "from env".to_string());
+    spec.stdin = Some(b"piped input\n".to_vec());
+    let mut task = CommandTask::new(spec);
+
+    let output = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("scripts")
+        .join("command_cat.out");
+    let error = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("scripts")
+        .join("command_cat.err");
+    let output_file = File::create(&output).expect("Failed to create output file");
+    let error_file = File::create(&error).expect("Failed to create error file");
+
+    task.start(output_file, error_file);
+    sleep(std::time::Duration::from_secs(1));
+    assert!(matches!(task.status(), RunnableStatus::Finished));
+
+    let output = std::fs::read_to_string(output).expect("Failed to read output file");
+    assert_eq!(output, "from env\npiped input\n");
+}
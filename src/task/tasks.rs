@@ -14,4 +14,7 @@ pub(crate) trait Runnable {
     fn start(&mut self, output: File, error: File) -> RunnableStatus;
     fn status(&self) -> RunnableStatus;
     fn kill(&mut self) -> RunnableStatus;
+    // Deliver an arbitrary Unix signal (e.g. SIGHUP, SIGINT, SIGUSR1) to a running
+    // task. Implementations target the task's whole process group.
+    fn signal(&mut self, sig: i32) -> RunnableStatus;
 }
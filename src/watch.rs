@@ -0,0 +1,213 @@
+// Event-driven trigger source, complementing the calendar scheduler. Inspired by
+// watchexec: a `WatchTrigger` watches a set of paths and fires an associated
+// `Runnable` when files underneath them are created, modified or deleted. Optional glob
+// filters narrow the firing set to matching paths (e.g. `src/**`). A burst of
+// filesystem events is debounced into a single launch within a configurable window,
+// and an optional `WeekSchedule` gate suppresses launches during `Busy` time. The
+// trigger drives its one task through the same `Runnable`/`RunnableStatus` lifecycle as
+// the calendar scheduler; it does not share `WindowScheduler`'s task queue.
+use crate::schedule::{TimeStatus, WeekSchedule};
+use crate::task::tasks::{Runnable, RunnableStatus};
+use chrono::{Datelike, Local, Timelike};
+use notify::{RecommendedWatcher, RecursiveMode, Watcher};
+use std::fs::File;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{channel, Receiver, RecvTimeoutError};
+use std::time::Duration;
+
+// Default quiet period: once events stop for this long, the coalesced burst fires.
+const DEFAULT_DEBOUNCE: Duration = Duration::from_millis(500);
+
+pub(crate) struct WatchTrigger {
+    paths: Vec<PathBuf>,
+    patterns: Vec<String>,
+    debounce: Duration,
+    task: Box<dyn Runnable>,
+    schedule: Option<WeekSchedule>,
+    output_dir: PathBuf,
+}
+
+impl WatchTrigger {
+    pub(crate) fn new(task: Box<dyn Runnable>, output_dir: PathBuf) -> WatchTrigger {
+        WatchTrigger {
+            paths: Vec::new(),
+            patterns: Vec::new(),
+            debounce: DEFAULT_DEBOUNCE,
+            task,
+            schedule: None,
+            output_dir,
+        }
+    }
+
+    // Add a path (file or directory, watched recursively) to the trigger set.
+    pub(crate) fn watch(mut self, path: PathBuf) -> WatchTrigger {
+        self.paths.push(path);
+        self
+    }
+
+    // Restrict firing to events whose path matches a glob (e.g. `src/**`). With no
+    // filters every change under a watched path fires; with one or more, an event fires
+    // the task only if at least one changed path matches a filter. `*` and `?` stop at a
+    // path separator, `**` spans them. Patterns are matched against the event path made
+// relative to the watched path that contains it.
+    pub(crate) fn filter(mut self, pattern: &str) -> WatchTrigger {
+        self.patterns.push(pattern.to_string());
+        self
+    }
+
+    pub(crate) fn with_debounce(mut self, debounce: Duration) -> WatchTrigger {
+        self.debounce = debounce;
+        self
+    }
+
+    // Gate launches so the task never fires during a `Busy` block.
+    pub(crate) fn with_schedule(mut self, schedule: WeekSchedule) -> WatchTrigger {
+        self.schedule = Some(schedule);
+        self
+    }
+
+    // Block watching the configured paths, coalescing each burst of events into a
+    // single launch. Returns if the watcher's channel closes.
+    pub(crate) fn run(&mut self) -> notify::Result<()> {
+        let (tx, rx) = channel();
+        let mut watcher = RecommendedWatcher::new(
+            move |res| {
+                let _ = tx.send(res);
+            },
+            notify::Config::default(),
+        )?;
+        for path in &self.paths {
+            watcher.watch(path, RecursiveMode::Recursive)?;
+        }
+
+        while let Some(matched) = self.wait_for_burst(&rx) {
+            if matched && self.slot_is_free() {
+                self.launch();
+            }
+        }
+        Ok(())
+    }
+
+    // Wait for the first event, then drain further events until the stream stays quiet
+    // for `debounce`. Returns `Some(matched)` once the burst settles, where `matched`
+    // reports whether any event in the burst passed the glob filters, or `None` once the
+    // channel is disconnected.
+    fn wait_for_burst(&self, rx: &Receiver<notify::Result<notify::Event>>) -> Option<bool> {
+        let mut matched = match rx.recv() {
+            Ok(res) => self.event_matches(&res),
+            Err(_) => return None,
+        };
+        loop {
+            match rx.recv_timeout(self.debounce) {
+                Ok(res) => matched |= self.event_matches(&res),
+                Err(RecvTimeoutError::Timeout) => return Some(matched),
+                Err(RecvTimeoutError::Disconnected) => return None,
+            }
+        }
+    }
+
+    // True when there are no glob filters, or some path in the event matches one.
+    fn event_matches(&self, res: &notify::Result<notify::Event>) -> bool {
+        if self.patterns.is_empty() {
+            return true;
+        }
+        match res {
+            Ok(event) => event
+                .paths
+                .iter()
+                .any(|path| path_matches_filters(&self.paths, &self.patterns, path)),
+            Err(_) => false,
+        }
+    }
+
+    // True when there is no schedule gate, or the current wall-clock slot is `Free`.
+    fn slot_is_free(&self) -> bool {
+        match &self.schedule {
+            Some(schedule) => {
+                let now = Local::now();
+                let time = now.time().with_nanosecond(0).unwrap_or(now.time());
+                schedule.get_time_status(now.weekday(), time) == TimeStatus::Free
+            }
+            None => true,
+        }
+    }
+
+    fn launch(&mut self) -> RunnableStatus {
+        let out = self.output_dir.join("watch.out");
+        let err = self.output_dir.join("watch.err");
+        match (File::create(out), File::create(err)) {
+            (Ok(out), Ok(err)) => self.task.start(out, err),
+            _ => RunnableStatus::Error("could not open watch output files".to_string()),
+        }
+    }
+}
+
+// Match `path` against `patterns`, first making it relative to whichever watched `root`
+// contains it. `notify` reports absolute paths for recursively-watched directories, so an
+// anchored filter like `src/**` is matched against the path below the watched root rather
+// than the absolute path it would never prefix.
+fn path_matches_filters(roots: &[PathBuf], patterns: &[String], path: &Path) -> bool {
+    let relative = roots
+        .iter()
+        .find_map(|root| path.strip_prefix(root).ok())
+        .unwrap_or(path);
+    let relative = relative.to_string_lossy();
+    patterns.iter().any(|pat| glob_match(pat, &relative))
+}
+
+// Match `text` against a shell-style glob. `?` matches one non-separator character, `*`
+// matches a run of non-separator characters, and `**` matches any run including `/`.
+fn glob_match(pattern: &str, text: &str) -> bool {
+    glob_bytes(pattern.as_bytes(), text.as_bytes())
+}
+
+fn glob_bytes(pattern: &[u8], text: &[u8]) -> bool {
+    match pattern.first() {
+        None => text.is_empty(),
+        Some(b'*') if pattern.get(1) == Some(&b'*') => {
+            // `**` spans path separators: try matching the remainder at every offset.
+            let rest = &pattern[2..];
+            (0..=text.len()).any(|i| glob_bytes(rest, &text[i..]))
+        }
+        Some(b'*') => {
+            // `*` consumes zero or more characters but never crosses a separator.
+            if glob_bytes(&pattern[1..], text) {
+                return true;
+            }
+            matches!(text.first(), Some(&c) if c != b'/') && glob_bytes(pattern, &text[1..])
+        }
+        Some(b'?') => matches!(text.first(), Some(&c) if c != b'/') && glob_bytes(&pattern[1..], &text[1..]),
+        Some(&c) => matches!(text.first(), Some(&t) if t == c) && glob_bytes(&pattern[1..], &text[1..]),
+    }
+}
+
+#[test]
+fn test_glob_match() {
+    // `**` spans directories; `*` does not.
+    assert!(glob_match("src/**", "src/app/main.rs"));
+    assert!(glob_match("src/*.rs", "src/main.rs"));
+    assert!(!glob_match("src/*.rs", "src/app/main.rs"));
+    // `?` matches a single non-separator character.
+    assert!(glob_match("a?c", "abc"));
+    assert!(!glob_match("a?c", "a/c"));
+    assert!(!glob_match("src/**", "docs/readme.md"));
+}
+
+#[test]
+fn test_filter_relative_to_watched_root() {
+    let roots = vec![PathBuf::from("/abs/proj")];
+    let patterns = vec!["src/**".to_string()];
+    // An absolute event path under the watched root matches the anchored `src/**`
+    // filter once it is made relative to the root.
+    assert!(path_matches_filters(
+        &roots,
+        &patterns,
+        Path::new("/abs/proj/src/foo.rs")
+    ));
+    // A change elsewhere under the root does not fire.
+    assert!(!path_matches_filters(
+        &roots,
+        &patterns,
+        Path::new("/abs/proj/docs/readme.md")
+    ));
+}